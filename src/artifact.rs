@@ -0,0 +1,145 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{bail, Context};
+use serde::Deserialize;
+
+use crate::config::DEFAULT_PROJECTS_FOLDER;
+
+/// `cosmwasm/workspace-optimizer`/`cosmwasm/rust-optimizer` image tag used for an
+/// [`Artifact::Git`] when the owning [`crate::config::TestConfig`] doesn't pin one.
+pub const DEFAULT_OPTIMIZER_VERSION: &str = "0.14.0";
+
+/// Where to load a contract's compiled bytecode from.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Artifact {
+    /// A prebuilt `.wasm` file already on disk.
+    Local(String),
+    /// A contract living in a git repository, built byte-reproducibly through the
+    /// `cosmwasm/workspace-optimizer` (or `rust-optimizer` for single-crate repos) Docker image
+    /// instead of being vendored as a prebuilt `.wasm`.
+    Git {
+        /// Git URL to clone/fetch.
+        repo: String,
+        /// Branch or tag to check out. A commit SHA is not accepted: the first checkout is a
+        /// shallow `git clone --branch`, which only takes a ref name.
+        rev: String,
+        /// Cargo package name inside the repo, used as the checkout directory name and to name
+        /// the optimizer's build-cache volume.
+        package: String,
+        /// Contract name, used to resolve `artifacts/<contract>.wasm` once built.
+        contract: String,
+    },
+}
+
+impl Artifact {
+    /// Resolve this artifact to a local `.wasm` path, cloning and building it first if it's a
+    /// [`Artifact::Git`]. `optimizer_version` overrides [`DEFAULT_OPTIMIZER_VERSION`]; pass
+    /// [`crate::config::TestConfig::optimizer_version`] so a whole suite can pin one image.
+    pub fn get_wasm_path(&self, optimizer_version: Option<&str>) -> anyhow::Result<PathBuf> {
+        match self {
+            Artifact::Local(path) => Ok(PathBuf::from(path)),
+            Artifact::Git {
+                repo,
+                rev,
+                package,
+                contract,
+            } => build_git_artifact(repo, rev, package, contract, optimizer_version),
+        }
+    }
+}
+
+fn build_git_artifact(
+    repo: &str,
+    rev: &str,
+    package: &str,
+    contract: &str,
+    optimizer_version: Option<&str>,
+) -> anyhow::Result<PathBuf> {
+    let checkout_dir = PathBuf::from(DEFAULT_PROJECTS_FOLDER).join(package);
+    clone_or_fetch(repo, rev, &checkout_dir)?;
+
+    let optimizer_version = optimizer_version.unwrap_or(DEFAULT_OPTIMIZER_VERSION);
+    // A single-crate repo has no `contracts/` workspace member directory; fall back to the
+    // lighter `rust-optimizer` image the same way the upstream CosmWasm contracts do.
+    let image = if checkout_dir.join("contracts").is_dir() {
+        format!("cosmwasm/workspace-optimizer:{optimizer_version}")
+    } else {
+        format!("cosmwasm/rust-optimizer:{optimizer_version}")
+    };
+
+    let mount = format!("{}:/code", checkout_dir.canonicalize()?.display());
+    let status = Command::new("docker")
+        .args([
+            "run",
+            "--rm",
+            "-v",
+            &mount,
+            "--mount",
+            &format!("type=volume,source={package}_cache,target=/target"),
+            "--mount",
+            "type=volume,source=registry_cache,target=/usr/local/cargo/registry",
+            &image,
+        ])
+        .status()
+        .with_context(|| format!("running optimizer image {image} for {package}"))?;
+
+    if !status.success() {
+        bail!("optimizer image {image} exited with {status} while building {package}");
+    }
+
+    Ok(checkout_dir
+        .join("artifacts")
+        .join(format!("{contract}.wasm")))
+}
+
+fn clone_or_fetch(repo: &str, rev: &str, dir: &Path) -> anyhow::Result<()> {
+    if dir.join(".git").exists() {
+        // `--depth 1` keeps a re-fetch as shallow as the initial clone below; without it, a
+        // single re-run against a long-lived repo would silently pull its entire history.
+        run_git(dir, &["fetch", "--depth", "1", "origin", rev])?;
+    } else {
+        if let Some(parent) = dir.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("creating {}", parent.display()))?;
+        }
+        // `--branch` + `--depth 1` only fetches the tip commit of `rev`, not the repo's whole
+        // history, since a checkout build only ever needs that one revision's source tree.
+        // `--branch` only takes a branch or tag name, so a `rev` that is a commit SHA makes this
+        // clone fail; there is no non-shallow fallback.
+        run_git(
+            Path::new("."),
+            &[
+                "clone",
+                "--depth",
+                "1",
+                "--branch",
+                rev,
+                repo,
+                &dir.to_string_lossy(),
+            ],
+        )?;
+        return Ok(());
+    }
+
+    // `rev` may be a branch name rather than a SHA, in which case the local branch left over
+    // from the initial clone is stale relative to what was just fetched. Detach onto the tip of
+    // the fetch instead of re-checking out `rev`, so a re-run against an updated branch actually
+    // picks up the new commits instead of silently rebuilding the old bytecode.
+    run_git(dir, &["checkout", "--detach", "FETCH_HEAD"])
+}
+
+fn run_git(dir: &Path, args: &[&str]) -> anyhow::Result<()> {
+    let status = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .with_context(|| format!("running `git {}` in {}", args.join(" "), dir.display()))?;
+
+    if !status.success() {
+        bail!("`git {}` failed with {status}", args.join(" "));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,19 @@
+use cosmwasm_std::BlockInfo;
+
+/// Block-height/time control surface for a `cw-it` runner backend.
+///
+/// [`MultiTestRunner`](crate::multi_test::runner::MultiTestRunner) implements it by mutating
+/// `cw_multi_test::App`'s `BlockInfo` directly. The RPC runner does not implement it.
+pub trait BlockTime {
+    /// Advance the chain clock by `seconds`, without changing the block height.
+    fn advance_time(&mut self, seconds: u64);
+
+    /// Advance the chain by `n` blocks.
+    fn advance_blocks(&mut self, n: u64);
+
+    /// Overwrite the current `BlockInfo` wholesale.
+    fn set_block(&mut self, block: BlockInfo);
+
+    /// The current `BlockInfo`.
+    fn block_info(&self) -> BlockInfo;
+}
@@ -15,6 +15,11 @@ pub const DEFAULT_PROJECTS_FOLDER: &str = "cloned_repos";
 #[derive(Clone, Debug, Deserialize)]
 pub struct TestConfig {
     pub contracts: HashMap<String, Contract>,
+    /// `cosmwasm/workspace-optimizer`/`cosmwasm/rust-optimizer` image tag used to build any
+    /// `Artifact::Git` contract. Defaults to
+    /// [`crate::artifact::DEFAULT_OPTIMIZER_VERSION`] when unset.
+    #[serde(default)]
+    pub optimizer_version: Option<String>,
     #[cfg(feature = "rpc-runner")]
     pub rpc_runner_config: RpcRunnerConfig,
 }
@@ -0,0 +1,178 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use cosmwasm_std::{Binary, Coin, Empty, Storage, Uint128};
+use cw_multi_test::{BankKeeper, StargateKeeper, StargateQueryHandler};
+use osmosis_std::types::cosmos::bank::v1beta1::{
+    Metadata, QueryDenomMetadataResponse, QuerySupplyOfResponse, QueryTotalSupplyResponse,
+};
+use osmosis_std::types::cosmos::base::v1beta1::Coin as ProtoCoin;
+use prost::Message;
+
+/// A pluggable binding for an app-specific chain module.
+///
+/// The default [`MultiTestRunner`](super::runner::MultiTestRunner) only wires up the bank
+/// module, so contracts that depend on something else entirely (Osmosis gamm/poolmanager, a
+/// token factory, a protocol's own custom querier, ...) have no way to be simulated. Implement
+/// this trait for such a module and pass it to
+/// [`MultiTestRunner::with_modules`](super::runner::MultiTestRunner::with_modules) to register
+/// its queries and message executors on the runner's `StargateKeeper` alongside the built-ins.
+pub trait StargateModule {
+    /// Register this module's query handlers, keyed by their gRPC query path.
+    fn register_queries(&self, keeper: &mut StargateKeeper<Empty, Empty>);
+
+    /// Register this module's message executors, keyed by the Cosmos `type_url`s they handle.
+    fn register_messages(&self, keeper: &mut StargateKeeper<Empty, Empty>);
+}
+
+/// Per-denom running total, updated every time
+/// [`MultiTestRunner::init_account`](super::runner::MultiTestRunner::init_account) mints
+/// balances into a fresh account — the only place this harness creates money, so summing mints
+/// is the same as summing supply. `Rc`/`RefCell` because the ledger is shared between the
+/// `BankModule` wired onto the `StargateKeeper` (which only ever reads it to answer queries) and
+/// `MultiTestRunner` itself (which writes to it on mint).
+#[derive(Clone, Default)]
+pub struct SupplyLedger(Rc<RefCell<BTreeMap<String, Uint128>>>);
+
+impl SupplyLedger {
+    pub fn record_mint(&self, minted: &[Coin]) {
+        let mut supply = self.0.borrow_mut();
+        for coin in minted {
+            *supply.entry(coin.denom.clone()).or_default() += coin.amount;
+        }
+    }
+
+    fn of(&self, denom: &str) -> Uint128 {
+        self.0.borrow().get(denom).copied().unwrap_or_default()
+    }
+
+    fn all(&self) -> Vec<Coin> {
+        self.0
+            .borrow()
+            .iter()
+            .map(|(denom, amount)| Coin {
+                denom: denom.clone(),
+                amount: *amount,
+            })
+            .collect()
+    }
+}
+
+/// Built-in bank module, answering `cosmos.bank.v1beta1` queries.
+///
+/// `AllBalances`/`Balance` are delegated straight to the `BankKeeper` cw-multi-test already
+/// tracks per-account balances in. `TotalSupply`/`SupplyOf` have no such built-in source of
+/// truth — `BankKeeper` has no running total, only per-account state — so they're answered from
+/// `supply`, a ledger this module keeps in step with every mint (see [`SupplyLedger`]).
+/// `DenomMetadata` has nothing backing it at all, so for any requested denom, minted or not, it
+/// returns the minimal `Metadata` a denom with no registered symbol/description/decimals would
+/// have on a real chain: `base`/`display` set to the denom itself, everything else empty.
+pub struct BankModule {
+    pub bank: BankKeeper,
+    pub supply: SupplyLedger,
+}
+
+impl BankModule {
+    pub fn new() -> Self {
+        Self {
+            bank: BankKeeper {},
+            supply: SupplyLedger::default(),
+        }
+    }
+
+    pub fn register_queries(&self, keeper: &mut StargateKeeper<Empty, Empty>) {
+        keeper.register_query(
+            "/cosmos.bank.v1beta1.Query/AllBalances",
+            Box::new(self.bank.clone()),
+        );
+        keeper.register_query(
+            "/cosmos.bank.v1beta1.Query/Balance",
+            Box::new(self.bank.clone()),
+        );
+        keeper.register_query(
+            "/cosmos.bank.v1beta1.Query/TotalSupply",
+            Box::new(TotalSupplyHandler(self.supply.clone())),
+        );
+        keeper.register_query(
+            "/cosmos.bank.v1beta1.Query/SupplyOf",
+            Box::new(SupplyOfHandler(self.supply.clone())),
+        );
+        keeper.register_query(
+            "/cosmos.bank.v1beta1.Query/DenomMetadata",
+            Box::new(DenomMetadataHandler(self.supply.clone())),
+        );
+    }
+}
+
+impl Default for BankModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct TotalSupplyHandler(SupplyLedger);
+
+impl StargateQueryHandler for TotalSupplyHandler {
+    fn execute(&self, _storage: &dyn Storage, _request: Binary) -> anyhow::Result<Binary> {
+        let response = QueryTotalSupplyResponse {
+            supply: self.0.all().into_iter().map(to_proto_coin).collect(),
+            pagination: None,
+        };
+        Ok(Binary(response.encode_to_vec()))
+    }
+}
+
+struct SupplyOfHandler(SupplyLedger);
+
+impl StargateQueryHandler for SupplyOfHandler {
+    fn execute(&self, _storage: &dyn Storage, request: Binary) -> anyhow::Result<Binary> {
+        let denom = decode_denom(&request)?;
+        let response = QuerySupplyOfResponse {
+            amount: Some(to_proto_coin(Coin {
+                denom: denom.clone(),
+                amount: self.0.of(&denom),
+            })),
+        };
+        Ok(Binary(response.encode_to_vec()))
+    }
+}
+
+struct DenomMetadataHandler(SupplyLedger);
+
+impl StargateQueryHandler for DenomMetadataHandler {
+    fn execute(&self, _storage: &dyn Storage, request: Binary) -> anyhow::Result<Binary> {
+        let denom = decode_denom(&request)?;
+        let response = QueryDenomMetadataResponse {
+            metadata: Some(Metadata {
+                description: String::new(),
+                denom_units: vec![],
+                base: denom.clone(),
+                display: denom,
+                name: String::new(),
+                symbol: String::new(),
+            }),
+        };
+        Ok(Binary(response.encode_to_vec()))
+    }
+}
+
+fn to_proto_coin(coin: Coin) -> ProtoCoin {
+    ProtoCoin {
+        denom: coin.denom,
+        amount: coin.amount.to_string(),
+    }
+}
+
+/// `QuerySupplyOfRequest`/`QueryDenomMetadataRequest` are both a single `denom: String` field at
+/// proto tag 1, so the raw request bytes can be decoded generically instead of needing a
+/// dedicated type per handler.
+fn decode_denom(request: &Binary) -> anyhow::Result<String> {
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    struct DenomRequest {
+        #[prost(string, tag = "1")]
+        denom: String,
+    }
+
+    Ok(DenomRequest::decode(request.as_slice())?.denom)
+}
@@ -1,15 +1,20 @@
-use anyhow::bail;
+use anyhow::Context;
 use cosmrs::{
     crypto::secp256k1::SigningKey,
     proto::cosmos::{base::abci::v1beta1::GasInfo},
 };
 use cosmwasm_std::{
-    coin, Addr, BankMsg, Binary, Coin, CosmosMsg, Empty, QueryRequest, StakingMsg, WasmMsg,
+    coin, Addr, BankMsg, Binary, BlockInfo, Coin, CosmosMsg, DistributionMsg, Empty, GovMsg,
+    QueryRequest, StakingMsg, VoteOption, WasmMsg,
 };
-use cw_multi_test::{BankKeeper, BankSudo, BasicAppBuilder, StargateKeeper, StargateQueryHandler};
+use cw_multi_test::{BankSudo, BasicAppBuilder, StargateKeeper, StargateQueryHandler};
 use osmosis_std::types::{
     cosmos::{
         bank::v1beta1::MsgSend,
+        distribution::v1beta1::{
+            MsgFundCommunityPool, MsgSetWithdrawAddress, MsgWithdrawDelegatorReward,
+        },
+        gov::v1beta1::MsgVote,
         staking::v1beta1::{MsgBeginRedelegate, MsgDelegate, MsgUndelegate},
     },
     cosmwasm::wasm::v1::{
@@ -25,22 +30,71 @@ use test_tube::{
     SigningAccount,
 };
 
-use crate::traits::{ContractType, WasmRunner};
+use crate::{
+    artifact::Artifact,
+    block::BlockTime,
+    traits::{ContractType, WasmRunner},
+};
+
+use super::{
+    modules::{BankModule, StargateModule, SupplyLedger},
+    vm_contract::VmContract,
+};
 
-use super::modules::BankModule;
+/// Average seconds between blocks, used by [`BlockTime::advance_blocks`] to keep the chain
+/// clock moving in step with the height.
+const AVG_BLOCK_TIME_SECONDS: u64 = 5;
 
 pub struct MultiTestRunner<'a> {
     pub app: cw_multi_test::App,
     pub address_prefix: &'a str,
+    /// `cosmwasm/workspace-optimizer`/`cosmwasm/rust-optimizer` image tag used to build any
+    /// `Artifact::Git` contract stored through this runner. Set from
+    /// [`crate::config::TestConfig::optimizer_version`] via [`Self::with_optimizer_version`] so
+    /// a whole suite can pin one image; `None` falls back to `Artifact`'s own default.
+    pub optimizer_version: Option<String>,
+    /// Backs the bank module's `TotalSupply`/`SupplyOf`/`DenomMetadata` answers. Updated by
+    /// [`Self::init_account`] on every mint; shared with the `BankModule` registered on the
+    /// `StargateKeeper` in [`Self::with_modules`].
+    supply: SupplyLedger,
 }
 
-const BANK_MODULE: BankModule = BankModule(BankKeeper {});
+// `osmosis_std`'s gov `VoteOption` is a raw protobuf enum (`i32`). `0` (`VOTE_OPTION_UNSPECIFIED`)
+// and anything outside the known range aren't real votes, so they're rejected rather than
+// silently coerced into a valid option the chain would have refused.
+fn vote_option_from_i32(option: i32) -> Result<VoteOption, RunnerError> {
+    match option {
+        1 => Ok(VoteOption::Yes),
+        2 => Ok(VoteOption::Abstain),
+        3 => Ok(VoteOption::No),
+        4 => Ok(VoteOption::NoWithVeto),
+        _ => Err(RunnerError::GenericError(format!(
+            "invalid gov VoteOption: {option}"
+        ))),
+    }
+}
 
 impl<'a> MultiTestRunner<'a> {
     pub fn new(address_prefix: &'a str) -> Self {
-        // Setup stargate keeper with bank module support
+        Self::with_modules(address_prefix, vec![])
+    }
+
+    /// Build a runner whose `StargateKeeper` also carries the queries and message executors of
+    /// `modules`, on top of the always-on bank module. Use this when a contract under test
+    /// depends on a chain module cw-it doesn't support out of the box (Osmosis gamm/poolmanager,
+    /// a token factory, a protocol's own custom querier, ...).
+    pub fn with_modules(
+        address_prefix: &'a str,
+        modules: Vec<Box<dyn StargateModule>>,
+    ) -> Self {
+        // Setup stargate keeper with bank module support plus any custom modules
+        let bank_module = BankModule::new();
         let mut stargate_keeper = StargateKeeper::new();
-        BANK_MODULE.register_queries(&mut stargate_keeper);
+        bank_module.register_queries(&mut stargate_keeper);
+        for module in &modules {
+            module.register_queries(&mut stargate_keeper);
+            module.register_messages(&mut stargate_keeper);
+        }
 
         // Construct app
         let app = BasicAppBuilder::<Empty, Empty>::new()
@@ -50,9 +104,19 @@ impl<'a> MultiTestRunner<'a> {
         Self {
             app,
             address_prefix,
+            optimizer_version: None,
+            supply: bank_module.supply,
         }
     }
 
+    /// Pin the `cosmwasm/workspace-optimizer`/`cosmwasm/rust-optimizer` image tag used to build
+    /// any `Artifact::Git` contract stored through this runner, e.g. from
+    /// `test_config.optimizer_version`.
+    pub fn with_optimizer_version(mut self, optimizer_version: Option<String>) -> Self {
+        self.optimizer_version = optimizer_version;
+        self
+    }
+
     // TODO: move to trait
     pub fn init_account(&self, initial_balance: &[Coin]) -> RunnerResult<SigningAccount> {
         // Create a random signing account
@@ -77,12 +141,36 @@ impl<'a> MultiTestRunner<'a> {
                     .into(),
                 )
                 .unwrap();
+            self.supply.record_mint(initial_balance);
         }
 
         Ok(account)
     }
 }
 
+impl BlockTime for MultiTestRunner<'_> {
+    fn advance_time(&mut self, seconds: u64) {
+        self.app.update_block(|block| {
+            block.time = block.time.plus_seconds(seconds);
+        });
+    }
+
+    fn advance_blocks(&mut self, n: u64) {
+        self.app.update_block(|block| {
+            block.height += n;
+            block.time = block.time.plus_seconds(n * AVG_BLOCK_TIME_SECONDS);
+        });
+    }
+
+    fn set_block(&mut self, block: BlockInfo) {
+        self.app.update_block(|b| *b = block);
+    }
+
+    fn block_info(&self) -> BlockInfo {
+        self.app.block_info()
+    }
+}
+
 impl Runner<'_> for MultiTestRunner<'_> {
     fn execute_cosmos_msgs<S>(
         &self,
@@ -269,8 +357,52 @@ impl Runner<'_> for MultiTestRunner<'_> {
                         ),
                     }))
                 }
+                // DistributionMsg
+                MsgWithdrawDelegatorReward::TYPE_URL => {
+                    let msg = MsgWithdrawDelegatorReward::decode(msg.value.as_slice())
+                        .map_err(DecodeError::ProtoDecodeError)?;
+                    Ok(CosmosMsg::<Empty>::Distribution(
+                        DistributionMsg::WithdrawDelegatorReward {
+                            validator: msg.validator_address,
+                        },
+                    ))
+                }
+                MsgSetWithdrawAddress::TYPE_URL => {
+                    let msg = MsgSetWithdrawAddress::decode(msg.value.as_slice())
+                        .map_err(DecodeError::ProtoDecodeError)?;
+                    Ok(CosmosMsg::<Empty>::Distribution(
+                        DistributionMsg::SetWithdrawAddress {
+                            address: msg.withdraw_address,
+                        },
+                    ))
+                }
+                MsgFundCommunityPool::TYPE_URL => {
+                    let msg = MsgFundCommunityPool::decode(msg.value.as_slice())
+                        .map_err(DecodeError::ProtoDecodeError)?;
+                    Ok(CosmosMsg::<Empty>::Distribution(
+                        DistributionMsg::FundCommunityPool {
+                            amount: msg
+                                .amount
+                                .into_iter()
+                                .map(|c| coin(u128::from_str(&c.amount).unwrap(), c.denom))
+                                .collect(),
+                        },
+                    ))
+                }
+                // GovMsg
+                MsgVote::TYPE_URL => {
+                    let msg =
+                        MsgVote::decode(msg.value.as_slice()).map_err(DecodeError::ProtoDecodeError)?;
+                    Ok(CosmosMsg::<Empty>::Gov(GovMsg::Vote {
+                        proposal_id: msg.proposal_id,
+                        vote: vote_option_from_i32(msg.option)?,
+                    }))
+                }
                 _ => {
-                    // Else assume StargateMsg
+                    // Else assume StargateMsg. This also covers `MsgSubmitProposal`:
+                    // `cosmwasm_std`'s `GovMsg` has no proposal-submission variant upstream, so
+                    // it can't be translated into a native `CosmosMsg` and falls through here
+                    // like any other unsupported module.
                     Ok(CosmosMsg::<Empty>::Stargate {
                         type_url: msg.type_url.clone(),
                         value: msg.value.clone().into(),
@@ -306,7 +438,12 @@ impl<'a> WasmRunner<'a> for MultiTestRunner<'a> {
     ) -> Result<u64, anyhow::Error> {
         match code {
             ContractType::MultiTestContract(contract) => Ok(self.app.store_code(contract)),
-            ContractType::Artifact(_) => bail!("Artifact not supported for MultiTestRunner"),
+            ContractType::Artifact(artifact) => {
+                let path = artifact.get_wasm_path(self.optimizer_version.as_deref())?;
+                let code = std::fs::read(&path)
+                    .with_context(|| format!("reading wasm artifact at {}", path.display()))?;
+                Ok(self.app.store_code(Box::new(VmContract::new(code))))
+            }
         }
     }
 }
@@ -317,10 +454,18 @@ mod tests {
     use cosmwasm_std::{coin, to_binary, Event};
     
     use osmosis_std::types::{
-        cosmos::bank::v1beta1::QueryAllBalancesRequest,
+        cosmos::{
+            bank::v1beta1::{
+                QueryAllBalancesRequest, QueryAllBalancesResponse, QueryDenomMetadataRequest,
+                QuerySupplyOfRequest, QueryTotalSupplyRequest,
+            },
+            distribution::v1beta1::{MsgSetWithdrawAddress, MsgSetWithdrawAddressResponse},
+            gov::v1beta1::MsgVoteResponse,
+        },
         cosmwasm::wasm::v1::MsgInstantiateContractResponse,
     };
-    use test_tube::{Bank, Module};
+    use osmosis_std::types::cosmos::base::v1beta1::Coin as ProtoCoin;
+    use test_tube::{Bank, Module, Wasm};
 
     use crate::{artifact::Artifact, helpers::upload_wasm_file};
 
@@ -417,9 +562,6 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
-    // This test should panic because we are trying to upload a wasm contract to a MultiTestRunner
-    // which does not support wasm contracts.
     fn wasm_instantiate_contract() {
         let app = MultiTestRunner::new("osmo");
         let alice = app.init_account(&[coin(1000, "uosmo")]).unwrap();
@@ -450,6 +592,51 @@ mod tests {
         assert_eq!(res.events[0].ty, "instantiate".to_string());
     }
 
+    #[test]
+    fn wasm_execute_then_query_round_trip() {
+        let app = MultiTestRunner::new("osmo");
+        let alice = app.init_account(&[coin(1000, "uosmo")]).unwrap();
+
+        let code_id = upload_wasm_file(
+            &app,
+            &alice,
+            ContractType::Artifact(Artifact::Local(counter::WASM_PATH.to_string())),
+        )
+        .unwrap();
+
+        let wasm = Wasm::new(&app);
+        let contract_addr = wasm
+            .instantiate(
+                code_id,
+                &counter::InstantiateMsg { count: 17 },
+                Some(&alice.address()),
+                Some("counter"),
+                &[],
+                &alice,
+            )
+            .unwrap()
+            .data
+            .address;
+
+        // Exercises `VmContract::execute`, which mutates storage through `StorageAdapter`'s
+        // `Mut` side.
+        wasm.execute(
+            &contract_addr,
+            &counter::ExecuteMsg::Increment {},
+            &[],
+            &alice,
+        )
+        .unwrap();
+
+        // Exercises `VmContract::query`, which must see the mutation above through the same
+        // contract storage, but via `StorageAdapter`'s read-only `Ro` side.
+        let res: counter::GetCountResponse = wasm
+            .query(&contract_addr, &counter::QueryMsg::GetCount {})
+            .unwrap();
+
+        assert_eq!(res.count, 18);
+    }
+
     #[test]
     fn bank_send() {
         let app = MultiTestRunner::new("osmo");
@@ -478,6 +665,165 @@ mod tests {
         );
     }
 
+    #[test]
+    fn execute_multiple_raw_translates_distribution_set_withdraw_address() {
+        let app = MultiTestRunner::new("osmo");
+        let alice = app.init_account(&[coin(1000, "uatom")]).unwrap();
+        let bob = app.init_account(&[]).unwrap();
+
+        let msg = MsgSetWithdrawAddress {
+            delegator_address: alice.address(),
+            withdraw_address: bob.address(),
+        };
+
+        app.execute_multiple::<_, MsgSetWithdrawAddressResponse>(
+            &[(msg, MsgSetWithdrawAddress::TYPE_URL)],
+            &alice,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn execute_multiple_raw_translates_gov_vote() {
+        let app = MultiTestRunner::new("osmo");
+        let alice = app.init_account(&[coin(1000, "uatom")]).unwrap();
+
+        let msg = MsgVote {
+            proposal_id: 1,
+            voter: alice.address(),
+            option: 1, // VOTE_OPTION_YES
+        };
+
+        app.execute_multiple::<_, MsgVoteResponse>(&[(msg, MsgVote::TYPE_URL)], &alice)
+            .unwrap();
+    }
+
+    #[test]
+    fn execute_multiple_raw_rejects_invalid_gov_vote_option() {
+        let app = MultiTestRunner::new("osmo");
+        let alice = app.init_account(&[coin(1000, "uatom")]).unwrap();
+
+        let msg = MsgVote {
+            proposal_id: 1,
+            voter: alice.address(),
+            option: 0, // VOTE_OPTION_UNSPECIFIED, not a real vote
+        };
+
+        let err = app
+            .execute_multiple::<_, MsgVoteResponse>(&[(msg, MsgVote::TYPE_URL)], &alice)
+            .unwrap_err();
+
+        match err {
+            RunnerError::GenericError(msg) => assert!(msg.contains("invalid gov VoteOption")),
+            other => panic!("expected RunnerError::GenericError, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn advance_time_and_blocks() {
+        let mut app = MultiTestRunner::new("osmo");
+        let start = app.block_info();
+
+        app.advance_blocks(5);
+        let after_blocks = app.block_info();
+        assert_eq!(after_blocks.height, start.height + 5);
+        assert_eq!(
+            after_blocks.time,
+            start.time.plus_seconds(5 * AVG_BLOCK_TIME_SECONDS)
+        );
+
+        app.advance_time(100);
+        let after_time = app.block_info();
+        assert_eq!(after_time.height, after_blocks.height);
+        assert_eq!(after_time.time, after_blocks.time.plus_seconds(100));
+
+        let custom_block = BlockInfo {
+            height: 42,
+            time: after_time.time,
+            chain_id: after_time.chain_id.clone(),
+        };
+        app.set_block(custom_block);
+        assert_eq!(app.block_info().height, 42);
+    }
+
+    /// Query path a real chain has no opinion on, used by [`CustomBalanceModule`] below to prove
+    /// a caller-supplied `StargateModule`'s handler is actually reached rather than merely
+    /// accepted by `with_modules`.
+    const CUSTOM_BALANCE_QUERY_PATH: &str = "/cw.it.test.v1.Query/Balance";
+
+    /// Answers [`CUSTOM_BALANCE_QUERY_PATH`] with a fixed, made-up balance that can't come from
+    /// anywhere else (not a real account's funds, not the built-in bank module, which is
+    /// registered under the real `/cosmos.bank.v1beta1.Query/*` paths instead).
+    struct CustomBalanceHandler;
+
+    impl StargateQueryHandler for CustomBalanceHandler {
+        fn execute(&self, _storage: &dyn cosmwasm_std::Storage, _request: Binary) -> anyhow::Result<Binary> {
+            let response = QueryAllBalancesResponse {
+                balances: vec![ProtoCoin {
+                    denom: "custom".to_string(),
+                    amount: "42".to_string(),
+                }],
+                pagination: None,
+            };
+            Ok(Binary(response.encode_to_vec()))
+        }
+    }
+
+    /// A `StargateModule` that registers a real query handler, proving `with_modules` actually
+    /// routes calls to a caller-supplied module rather than only accepting and ignoring it.
+    struct CustomBalanceModule;
+
+    impl StargateModule for CustomBalanceModule {
+        fn register_queries(&self, keeper: &mut StargateKeeper<Empty, Empty>) {
+            keeper.register_query(CUSTOM_BALANCE_QUERY_PATH, Box::new(CustomBalanceHandler));
+        }
+
+        fn register_messages(&self, _keeper: &mut StargateKeeper<Empty, Empty>) {}
+    }
+
+    #[test]
+    fn with_modules_keeps_bank_module_working_alongside_a_custom_module() {
+        let app = MultiTestRunner::with_modules("osmo", vec![Box::new(CustomBalanceModule)]);
+        let alice = app.init_account(&[coin(1000, "uatom")]).unwrap();
+        let bob = app.init_account(&[]).unwrap();
+
+        let msgs = vec![cosmwasm_std::CosmosMsg::Bank(cosmwasm_std::BankMsg::Send {
+            to_address: bob.address(),
+            amount: vec![cosmwasm_std::Coin {
+                denom: "uatom".to_string(),
+                amount: 100u128.into(),
+            }],
+        })];
+
+        let res = app
+            .execute_cosmos_msgs::<MsgSendResponse>(&msgs, &alice)
+            .unwrap();
+
+        assert_eq!(res.events.len(), 1);
+    }
+
+    #[test]
+    fn with_modules_routes_queries_to_a_custom_stargate_module() {
+        let app = MultiTestRunner::with_modules("osmo", vec![Box::new(CustomBalanceModule)]);
+        let _alice = app.init_account(&[coin(1000, "uatom")]).unwrap();
+
+        let res: QueryAllBalancesResponse = app
+            .query(
+                CUSTOM_BALANCE_QUERY_PATH,
+                &QueryAllBalancesRequest {
+                    address: String::new(),
+                    pagination: None,
+                },
+            )
+            .unwrap();
+
+        // This could only have come from `CustomBalanceHandler`: alice's real balance is
+        // 1000uatom, and the built-in bank module answers a different path entirely.
+        assert_eq!(res.balances.len(), 1);
+        assert_eq!(res.balances[0].denom, "custom");
+        assert_eq!(res.balances[0].amount, "42");
+    }
+
     #[test]
     fn query_bank_through_test_tube_bank_module() {
         let app = MultiTestRunner::new("osmo");
@@ -496,4 +842,59 @@ mod tests {
         assert_eq!(res.balances[0].denom, "uatom".to_string());
         assert_eq!(res.balances[0].amount, "1000");
     }
+
+    #[test]
+    fn query_supply_of_through_test_tube_bank_module() {
+        let app = MultiTestRunner::new("osmo");
+        let _alice = app.init_account(&[coin(1000, "uatom")]).unwrap();
+        let _bob = app.init_account(&[coin(500, "uatom")]).unwrap();
+
+        let bank = Bank::new(&app);
+
+        let res = bank
+            .query_supply_of(&QuerySupplyOfRequest {
+                denom: "uatom".to_string(),
+            })
+            .unwrap();
+
+        let supply = res.amount.unwrap();
+        assert_eq!(supply.denom, "uatom".to_string());
+        assert_eq!(supply.amount, "1500");
+    }
+
+    #[test]
+    fn query_total_supply_through_test_tube_bank_module() {
+        let app = MultiTestRunner::new("osmo");
+        let _alice = app.init_account(&[coin(1000, "uatom")]).unwrap();
+        let _bob = app.init_account(&[coin(500, "uatom")]).unwrap();
+
+        let bank = Bank::new(&app);
+
+        let res = bank
+            .query_total_supply(&QueryTotalSupplyRequest { pagination: None })
+            .unwrap();
+
+        let uatom_supply = res
+            .supply
+            .iter()
+            .find(|c| c.denom == "uatom")
+            .expect("uatom should be in total supply");
+        assert_eq!(uatom_supply.amount, "1500");
+    }
+
+    #[test]
+    fn query_denom_metadata_through_test_tube_bank_module() {
+        let app = MultiTestRunner::new("osmo");
+        let _alice = app.init_account(&[coin(1000, "uatom")]).unwrap();
+
+        let bank = Bank::new(&app);
+
+        let res = bank
+            .query_denom_metadata(&QueryDenomMetadataRequest {
+                denom: "uatom".to_string(),
+            })
+            .unwrap();
+
+        assert_eq!(res.metadata.unwrap().base, "uatom".to_string());
+    }
 }
@@ -0,0 +1,288 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::vec::IntoIter;
+
+use anyhow::{anyhow, Context};
+use cosmwasm_std::{
+    Binary, Deps, DepsMut, Empty, Env, MessageInfo, Order, QuerierWrapper, Reply, Response,
+    Storage,
+};
+use cosmwasm_vm::{
+    call_execute, call_instantiate, call_migrate, call_query, call_reply, call_sudo, Backend,
+    BackendApi, BackendError, BackendResult, GasInfo, Instance, InstanceOptions, Record,
+    Storage as VmStorage,
+};
+use cw_multi_test::Contract;
+
+// The backend bridge below doesn't meter gas, the same way the rest of `MultiTestRunner` reports
+// `gas_wanted: 0, gas_used: 0` for every execution (see `Runner::execute_cosmos_msgs`). Wasm
+// execution gas is metered separately by `cosmwasm-vm` itself, so the `Instance` still needs a
+// real, large gas limit below — a zero limit (what `InstanceOptions::default()` would give us,
+// since the crate has no sane default) would make every call fail with an out-of-gas error
+// before running a single instruction.
+const NO_GAS: GasInfo = GasInfo::with_externally_used(0);
+
+/// Large enough that no real contract call exhausts it; `cosmwasm-vm` requires callers to pick a
+/// gas limit explicitly rather than defaulting to one (see e.g. `cosmwasm_vm::testing`'s own
+/// fixed `DEFAULT_GAS_LIMIT`).
+const GAS_LIMIT: u64 = 400_000_000_000_000;
+
+/// Wraps a compiled `.wasm` artifact so `cw-multi-test` can run the exact bytecode produced by
+/// the optimizer, rather than the native Rust entry points a `ContractWrapper` calls directly.
+/// Each entrypoint spins up a fresh `cosmwasm_vm::Instance` over the artifact bytes, backed by
+/// the `Storage`/`Querier` cw-multi-test hands us, and calls through to the matching
+/// `cosmwasm_vm::call_*` helper — the same path a real chain node uses to run wasm contracts.
+/// This is how wasm-only issues (float bans, memory limits, serialization quirks) that native
+/// multi-test contracts hide get caught inside the fast in-process harness.
+pub struct VmContract {
+    code: Vec<u8>,
+}
+
+impl VmContract {
+    pub fn new(code: Vec<u8>) -> Self {
+        Self { code }
+    }
+
+    fn instance<'a>(
+        &self,
+        storage: StorageRef<'a>,
+        querier: QuerierWrapper<'a, Empty>,
+    ) -> anyhow::Result<Instance<ApiAdapter, StorageAdapter<'a>, QuerierAdapter<'a>>> {
+        let backend = Backend {
+            api: ApiAdapter,
+            storage: StorageAdapter::new(storage),
+            querier: QuerierAdapter(querier),
+        };
+
+        let options = InstanceOptions {
+            gas_limit: GAS_LIMIT,
+            print_debug: false,
+        };
+
+        Instance::from_code(&self.code, backend, options, None)
+            .context("instantiating cosmwasm-vm Instance from artifact bytes")
+    }
+}
+
+impl Contract<Empty> for VmContract {
+    fn instantiate(
+        &self,
+        deps: DepsMut<Empty>,
+        env: Env,
+        info: MessageInfo,
+        msg: Vec<u8>,
+    ) -> anyhow::Result<Response<Empty>> {
+        let mut instance = self.instance(StorageRef::Mut(deps.storage), deps.querier)?;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &env, &info, &msg)
+            .context("calling instantiate on the wasm artifact")?
+            .into_result()
+            .map_err(|e| anyhow!(e))
+    }
+
+    fn execute(
+        &self,
+        deps: DepsMut<Empty>,
+        env: Env,
+        info: MessageInfo,
+        msg: Vec<u8>,
+    ) -> anyhow::Result<Response<Empty>> {
+        let mut instance = self.instance(StorageRef::Mut(deps.storage), deps.querier)?;
+        call_execute::<_, _, _, Empty>(&mut instance, &env, &info, &msg)
+            .context("calling execute on the wasm artifact")?
+            .into_result()
+            .map_err(|e| anyhow!(e))
+    }
+
+    fn query(&self, deps: Deps<Empty>, env: Env, msg: Vec<u8>) -> anyhow::Result<Binary> {
+        let mut instance = self.instance(StorageRef::Ro(deps.storage), deps.querier)?;
+        call_query(&mut instance, &env, &msg)
+            .context("calling query on the wasm artifact")?
+            .into_result()
+            .map(Binary)
+            .map_err(|e| anyhow!(e))
+    }
+
+    fn sudo(
+        &self,
+        deps: DepsMut<Empty>,
+        env: Env,
+        msg: Vec<u8>,
+    ) -> anyhow::Result<Response<Empty>> {
+        let mut instance = self.instance(StorageRef::Mut(deps.storage), deps.querier)?;
+        call_sudo::<_, _, _, Empty>(&mut instance, &env, &msg)
+            .context("calling sudo on the wasm artifact")?
+            .into_result()
+            .map_err(|e| anyhow!(e))
+    }
+
+    fn reply(
+        &self,
+        deps: DepsMut<Empty>,
+        env: Env,
+        msg: Reply,
+    ) -> anyhow::Result<Response<Empty>> {
+        let mut instance = self.instance(StorageRef::Mut(deps.storage), deps.querier)?;
+        call_reply::<_, _, _, Empty>(&mut instance, &env, &msg)
+            .context("calling reply on the wasm artifact")?
+            .into_result()
+            .map_err(|e| anyhow!(e))
+    }
+
+    fn migrate(
+        &self,
+        deps: DepsMut<Empty>,
+        env: Env,
+        msg: Vec<u8>,
+    ) -> anyhow::Result<Response<Empty>> {
+        let mut instance = self.instance(StorageRef::Mut(deps.storage), deps.querier)?;
+        call_migrate::<_, _, _, Empty>(&mut instance, &env, &msg)
+            .context("calling migrate on the wasm artifact")?
+            .into_result()
+            .map_err(|e| anyhow!(e))
+    }
+}
+
+/// Either side of cw-multi-test's storage access: mutable for instantiate/execute/sudo/reply/
+/// migrate, read-only for query (which must not persist state).
+enum StorageRef<'a> {
+    Mut(&'a mut dyn Storage),
+    Ro(&'a dyn Storage),
+}
+
+impl StorageRef<'_> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        match self {
+            StorageRef::Mut(s) => s.get(key),
+            StorageRef::Ro(s) => s.get(key),
+        }
+    }
+
+    fn range(
+        &self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Vec<Record> {
+        match self {
+            StorageRef::Mut(s) => s.range(start, end, order).collect(),
+            StorageRef::Ro(s) => s.range(start, end, order).collect(),
+        }
+    }
+}
+
+/// Adapts cw-multi-test's `Storage` to `cosmwasm_vm`'s gas-metered `Storage` trait. Gas isn't
+/// tracked (see [`NO_GAS`]); open iterators are kept in `iterators` and drained through to
+/// completion by `next`. A query's [`StorageRef::Ro`] rejects `set`/`remove` outright, since a
+/// query entrypoint must not mutate state.
+struct StorageAdapter<'a> {
+    inner: StorageRef<'a>,
+    iterators: RefCell<HashMap<u32, IntoIter<Record>>>,
+    next_iterator_id: RefCell<u32>,
+}
+
+impl<'a> StorageAdapter<'a> {
+    fn new(inner: StorageRef<'a>) -> Self {
+        Self {
+            inner,
+            iterators: RefCell::new(HashMap::new()),
+            next_iterator_id: RefCell::new(1),
+        }
+    }
+}
+
+impl VmStorage for StorageAdapter<'_> {
+    fn get(&self, key: &[u8]) -> BackendResult<Option<Vec<u8>>> {
+        (Ok(self.inner.get(key)), NO_GAS)
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) -> BackendResult<()> {
+        match &mut self.inner {
+            StorageRef::Mut(s) => {
+                s.set(key, value);
+                (Ok(()), NO_GAS)
+            }
+            StorageRef::Ro(_) => (
+                Err(BackendError::user_err("storage is read-only during query")),
+                NO_GAS,
+            ),
+        }
+    }
+
+    fn remove(&mut self, key: &[u8]) -> BackendResult<()> {
+        match &mut self.inner {
+            StorageRef::Mut(s) => {
+                s.remove(key);
+                (Ok(()), NO_GAS)
+            }
+            StorageRef::Ro(_) => (
+                Err(BackendError::user_err("storage is read-only during query")),
+                NO_GAS,
+            ),
+        }
+    }
+
+    fn scan(
+        &mut self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> BackendResult<u32> {
+        let records = self.inner.range(start, end, order);
+
+        let mut next_id = self.next_iterator_id.borrow_mut();
+        let id = *next_id;
+        *next_id += 1;
+        self.iterators
+            .borrow_mut()
+            .insert(id, records.into_iter());
+
+        (Ok(id), NO_GAS)
+    }
+
+    fn next(&mut self, iterator_id: u32) -> BackendResult<Option<Record>> {
+        let mut iterators = self.iterators.borrow_mut();
+        let result = match iterators.get_mut(&iterator_id) {
+            Some(iter) => Ok(iter.next()),
+            None => Err(BackendError::iterator_does_not_exist(iterator_id)),
+        };
+        (result, NO_GAS)
+    }
+}
+
+/// Adapts cw-multi-test's `QuerierWrapper` to `cosmwasm_vm`'s gas-metered `Querier` trait, so
+/// smart/raw queries issued from inside the artifact reach the same multi-test app state.
+struct QuerierAdapter<'a>(QuerierWrapper<'a, Empty>);
+
+impl cosmwasm_vm::Querier for QuerierAdapter<'_> {
+    fn query_raw(
+        &self,
+        request: &[u8],
+        _gas_limit: u64,
+    ) -> BackendResult<cosmwasm_std::SystemResult<cosmwasm_std::ContractResult<Binary>>> {
+        (Ok(self.0.raw_query(request)), NO_GAS)
+    }
+}
+
+/// `cosmwasm_vm`'s `BackendApi` only needs address validation/(de)canonicalization;
+/// `MultiTestRunner` has no chain-specific bech32 implementation of its own, so addresses pass
+/// through unchanged, the same way `cosmwasm_std::testing::MockApi` does.
+#[derive(Clone, Copy)]
+struct ApiAdapter;
+
+impl BackendApi for ApiAdapter {
+    fn addr_validate(&self, _input: &str) -> BackendResult<()> {
+        (Ok(()), NO_GAS)
+    }
+
+    fn addr_canonicalize(&self, human: &str) -> BackendResult<Vec<u8>> {
+        (Ok(human.as_bytes().to_vec()), NO_GAS)
+    }
+
+    fn addr_humanize(&self, canonical: &[u8]) -> BackendResult<String> {
+        (
+            String::from_utf8(canonical.to_vec())
+                .map_err(|e| BackendError::user_err(e.to_string())),
+            NO_GAS,
+        )
+    }
+}